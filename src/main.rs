@@ -4,7 +4,10 @@ fn main() {
 
 
 use std::cell::UnsafeCell;
-use crate::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 pub struct Cell<T> {
     value: UnsafeCell<T>,
@@ -34,6 +37,57 @@ impl<T> Cell<T> {
         // (because !Sync), and it is executing this function instead.
         unsafe { *self.value.get() }
     }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        // &mut self statically guarantees exclusive access, so no unsafe needed.
+        self.value.get_mut()
+    }
+}
+
+// A cell you can write to exactly once through a shared reference.
+pub struct OnceCell<T> {
+    value: UnsafeCell<Option<T>>,
+}
+
+// like Cell, !Sync is implied by UnsafeCell
+
+impl<T> OnceCell<T> {
+    pub fn new() -> Self {
+        OnceCell {
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        // SAFETY: once the inner Option becomes Some it is never overwritten or
+        // cleared, so the reference we hand out stays valid for the cell's life.
+        unsafe { &*self.value.get() }.as_ref()
+    }
+
+    pub fn set(&self, value: T) -> Result<(), T> {
+        // SAFETY: !Sync, so no other thread is touching the inner Option.
+        let slot = unsafe { &mut *self.value.get() };
+        if slot.is_some() {
+            return Err(value);
+        }
+        *slot = Some(value);
+        Ok(())
+    }
+
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if self.get().is_none() {
+            // set only fails if f itself initialised the cell; that value is kept
+            // and the one f returned is dropped.
+            let _ = self.set(f());
+        }
+        self.get().unwrap()
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -52,38 +106,102 @@ pub struct RefCell<T> {
 impl<T> RefCell<T> {
     pub fn new(value: T) -> Self {
         Self {
-            value: UnsafeCell::new(value)
-            state: Cell::New(RefState::Unshared),
+            value: UnsafeCell::new(value),
+            state: Cell::new(RefState::Unshared),
         }
     }
 
-    pub fn borrow_mut(&self) -> Option<RefMut<'_, T>> {
-        if let RefState::Unshared == self.state.get() {
+    pub fn try_borrow_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        if let RefState::Unshared = self.state.get() {
             self.state.set(RefState::Excusive);
-            Some(RefMut { refcell: self })
+            // SAFETY: no other references are out, so this unique lease is sound.
+            Ok(RefMut {
+                value: unsafe { &mut *self.value.get() },
+                state: &self.state,
+            })
         } else {
-            None
+            Err(BorrowMutError)
         }
     }
 
-    pub fn borrow(&self) -> Option<Ref<'_, T>>  {
+    pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
         match self.state.get() {
             RefState::Unshared => {
                 self.state.set(RefState::Shared(1));
-                Some(Ref { refcell: self }),
+                // SAFETY: no exclusive reference is out, so a shared one is fine.
+                Ok(Ref {
+                    value: unsafe { &*self.value.get() },
+                    state: &self.state,
+                })
             }
             RefState::Shared(n) => {
                 self.state.set(RefState::Shared(n + 1));
-                Some(Ref { refcell: self }),
+                // SAFETY: as above, only shared references are out.
+                Ok(Ref {
+                    value: unsafe { &*self.value.get() },
+                    state: &self.state,
+                })
             }
-            RefState::Excusive => None 
+            RefState::Excusive => Err(BorrowError),
         }
     }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.try_borrow().expect("already mutably borrowed")
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.try_borrow_mut().expect("already borrowed")
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        // &mut self proves exclusive access statically, so we skip the runtime
+        // borrow-state check entirely.
+        self.value.get_mut()
+    }
 }
 
-// This is trait we used to maintain borrow checker at runtime rather then compile time as we have used unsafe code 
+// Returned when a shared borrow is blocked by an outstanding exclusive borrow.
+#[derive(Debug)]
+pub struct BorrowError;
+
+// Returned when an exclusive borrow is blocked by any outstanding borrow.
+#[derive(Debug)]
+pub struct BorrowMutError;
+
+impl std::fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("already mutably borrowed")
+    }
+}
+
+impl std::fmt::Display for BorrowMutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("already borrowed")
+    }
+}
+
+impl std::error::Error for BorrowError {}
+impl std::error::Error for BorrowMutError {}
+
+// This is trait we used to maintain borrow checker at runtime rather then compile time as we have used unsafe code
+// The guard no longer names the original RefCell<T>: it carries the (possibly
+// projected) reference plus a separate handle to the shared borrow-count state,
+// so Ref::map can narrow to a field while Drop still decrements the right counter.
 pub struct Ref<'refcell, T> {
-    refcell: &'refcell RefCell<T>,
+    value: &'refcell T,
+    state: &'refcell Cell<RefState>,
+}
+
+impl<'refcell, T> Ref<'refcell, T> {
+    // Narrow a Ref to part of the borrowed value, keeping the borrow tracked.
+    pub fn map<U>(orig: Ref<'refcell, T>, f: impl FnOnce(&T) -> &U) -> Ref<'refcell, U> {
+        let state = orig.state;
+        let value = f(orig.value);
+        // don't let orig's Drop fire; the returned Ref now owns the count.
+        std::mem::forget(orig);
+        Ref { value, state }
+    }
 }
 
 impl<T> Deref for Ref<'_, T> {
@@ -93,35 +211,52 @@ impl<T> Deref for Ref<'_, T> {
         // a Ref is only created if no exclusive references have been given out.
         // once it is given out, state is set to Shared, so no exclusive references are given out.
         // so dereferencing into a shared reference is fine.
-        unsafe {&*self.Target.value.get()}
+        self.value
     }
 }
 
 impl<T> Drop for Ref<'_, T> {
-    fn drop(&self) {
-        match self.refcell.state.get() {
-            RefState::Exclusive | RefState::Unshared => unreachable!(),
+    fn drop(&mut self) {
+        match self.state.get() {
+            RefState::Excusive | RefState::Unshared => unreachable!(),
             RefState::Shared(1) => {
-                self.refcell.state.set(RefState::Unshared);
+                self.state.set(RefState::Unshared);
             }
             RefState::Shared(n) => {
-                self.refcell.state.set(RefState::Shared(n - 1));
+                self.state.set(RefState::Shared(n - 1));
             }
         }
     }
-
 }
 
 pub struct RefMut<'refcell, T> {
-    refcell: &'refcell RefCell<T>,
+    value: &'refcell mut T,
+    state: &'refcell Cell<RefState>,
+}
+
+impl<'refcell, T> RefMut<'refcell, T> {
+    // Narrow a RefMut to part of the borrowed value, keeping the borrow tracked.
+    pub fn map<U>(
+        orig: RefMut<'refcell, T>,
+        f: impl FnOnce(&mut T) -> &mut U,
+    ) -> RefMut<'refcell, U> {
+        let state = orig.state;
+        let ptr: *mut T = orig.value;
+        // don't let orig's Drop fire; the returned RefMut now owns the count.
+        std::mem::forget(orig);
+        // SAFETY: orig held the only reference to the value and we've forgotten
+        // it, so reborrowing through the raw pointer does not alias.
+        let value = f(unsafe { &mut *ptr });
+        RefMut { value, state }
+    }
 }
 
-impl<T> Deref for RefMut<'refcell, T> {
+impl<T> Deref for RefMut<'_, T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
         // SAFETY
         // see safety for DerefMut
-        unsafe {&*self.refcell.value.get()}
+        self.value
     }
 }
 
@@ -131,22 +266,298 @@ impl<T> std::ops::DerefMut for RefMut<'_, T> {
         // a RefMut is only created if no other references have been given out.
         // once it is given out, state is set to Exclusive, so no future references are given out.
         // so we have an exclusive lease on the inner value, so mutably dereferencing is fine.
-        unsafe { &mut *self.refcell.value.get() }
+        self.value
     }
 }
 
 impl<T> Drop for RefMut<'_, T> {
     fn drop(&mut self) {
-        match self.refcell.state.get() {
+        match self.state.get() {
             RefState::Shared(_) | RefState::Unshared => unreachable!(),
-            RefState::Exclusive => {
-                self.refcell.state.set(RefState::Unshared);
+            RefState::Excusive => {
+                self.state.set(RefState::Unshared);
+            }
+        }
+    }
+}
+
+// the heap-allocated inner; the count is a Cell so we can bump it through a shared &Rc
+struct RcInner<T> {
+    value: T,
+    count: Cell<usize>,
+}
+
+pub struct Rc<T> {
+    inner: NonNull<RcInner<T>>,
+    // so drop-check knows an Rc<T> owns an RcInner<T>, and hence a T
+    _marker: PhantomData<RcInner<T>>,
+}
+
+impl<T> Rc<T> {
+    pub fn new(value: T) -> Self {
+        let inner = Box::new(RcInner {
+            value,
+            count: Cell::new(1),
+        });
+        Rc {
+            // SAFETY: Box::into_raw never returns null
+            inner: unsafe { NonNull::new_unchecked(Box::into_raw(inner)) },
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &RcInner<T> {
+        // SAFETY: the inner box is only freed when the last Rc goes away, and
+        // we are holding one, so the pointer is still valid.
+        unsafe { self.inner.as_ref() }
+    }
+}
+
+impl<T> Clone for Rc<T> {
+    fn clone(&self) -> Self {
+        let inner = self.inner();
+        inner.count.set(inner.count.get() + 1);
+        Rc {
+            inner: self.inner,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for Rc<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.inner().value
+    }
+}
+
+impl<T> Drop for Rc<T> {
+    fn drop(&mut self) {
+        let inner = self.inner();
+        let c = inner.count.get();
+        if c == 1 {
+            // we're the last Rc, so there are no references to inner left.
+            // SAFETY: we reconstruct the Box we leaked in `new` and let it drop,
+            // which reclaims the allocation and drops the inner value.
+            let _ = unsafe { Box::from_raw(self.inner.as_ptr()) };
+        } else {
+            // there are other Rcs, so don't drop the Box!
+            inner.count.set(c - 1);
+        }
+    }
+}
+
+// Rc is deliberately !Send + !Sync: the strong count is a single-threaded Cell,
+// so cloning/dropping from multiple threads would race. NonNull is already !Send/!Sync.
+
+// the single high bit means "exclusively borrowed"; every other value is a
+// shared-borrow count (0 == unshared).
+const EXCLUSIVE: usize = 1 << (usize::BITS - 1);
+
+// Like RefCell, but the borrow state lives in one AtomicUsize instead of a
+// !Sync Cell, so this can sit behind an Arc and be shared across threads.
+pub struct AtomicRefCell<T> {
+    value: UnsafeCell<T>,
+    // 0 = unshared, 1..=isize::MAX = that many shared borrows, EXCLUSIVE = borrowed mut
+    state: AtomicUsize,
+}
+
+// SAFETY: all access to `value` is gated through the atomic borrow state: the
+// shared path only increments when the exclusive bit is clear, and the
+// exclusive path only wins from 0, so there is never more than one &mut or any
+// &mut alongside a &.
+unsafe impl<T: Send> Sync for AtomicRefCell<T> {}
+
+impl<T> AtomicRefCell<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            state: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn borrow(&self) -> Option<AtomicRef<'_, T>> {
+        // CAS loop instead of a blind fetch_add: we only ever publish count+1
+        // when the exclusive bit is clear, so an exclusive unlock's store(0) can
+        // neither clobber a speculative increment nor be undone into underflow.
+        let mut cur = self.state.load(Ordering::Relaxed);
+        loop {
+            if cur & EXCLUSIVE != 0 {
+                return None;
+            }
+            match self.state.compare_exchange_weak(
+                cur,
+                cur + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(AtomicRef { orc: self }),
+                Err(actual) => cur = actual,
             }
         }
     }
+
+    pub fn borrow_mut(&self) -> Option<AtomicRefMut<'_, T>> {
+        match self
+            .state
+            .compare_exchange(0, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => Some(AtomicRefMut { orc: self }),
+            Err(_) => None,
+        }
+    }
 }
 
-// RefCell - its safe dynamically check borrowing 
+pub struct AtomicRef<'orc, T> {
+    orc: &'orc AtomicRefCell<T>,
+}
+
+impl<T> Deref for AtomicRef<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: an AtomicRef only exists while the state is a shared count, so
+        // no exclusive borrow is out and handing out a shared reference is fine.
+        unsafe { &*self.orc.value.get() }
+    }
+}
+
+impl<T> Drop for AtomicRef<'_, T> {
+    fn drop(&mut self) {
+        self.orc.state.fetch_sub(1, Ordering::Release);
+    }
+}
+
+pub struct AtomicRefMut<'orc, T> {
+    orc: &'orc AtomicRefCell<T>,
+}
+
+impl<T> Deref for AtomicRefMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: see DerefMut; we hold the exclusive lease.
+        unsafe { &*self.orc.value.get() }
+    }
+}
+
+impl<T> std::ops::DerefMut for AtomicRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: an AtomicRefMut only exists while the state is EXCLUSIVE, so we
+        // have a unique lease on the value.
+        unsafe { &mut *self.orc.value.get() }
+    }
+}
+
+impl<T> Drop for AtomicRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.orc.state.store(0, Ordering::Release);
+    }
+}
+
+// A spin lock: the locked flag lives in an AtomicBool and we busy-wait to take
+// it. `with_lock` runs a closure while the lock is held.
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: the AtomicBool serialises access so only one thread touches `value`
+// at a time; T must be Send because it effectively moves between threads.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        // test-and-test-and-set: after a failed CAS, spin on a relaxed load until
+        // the lock looks free before retrying, to avoid bouncing the cache line.
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            while self.locked.load(Ordering::Relaxed) {
+                std::hint::spin_loop();
+            }
+        }
+        // SAFETY: we hold the lock, so we have exclusive access to `value`.
+        let r = f(unsafe { &mut *self.value.get() });
+        // Release pairs with the Acquire above so the protected writes are
+        // visible to the next thread that takes the lock.
+        self.locked.store(false, Ordering::Release);
+        r
+    }
+}
+
+// A Mutex<T> with the same atomic spin acquisition, but handing out an RAII
+// guard that derefs to the contents and unlocks on Drop.
+pub struct Mutex<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: same argument as SpinLock — the AtomicBool gives mutual exclusion.
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            // spin on a relaxed load (test-and-test-and-set) before retrying the CAS
+            while self.locked.load(Ordering::Relaxed) {
+                std::hint::spin_loop();
+            }
+        }
+        MutexGuard { mutex: self }
+    }
+
+    pub fn with_lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut guard = self.lock();
+        f(&mut *guard)
+    }
+}
+
+pub struct MutexGuard<'mutex, T> {
+    mutex: &'mutex Mutex<T>,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: holding the guard means we hold the lock.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> std::ops::DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: holding the guard means we hold the lock exclusively.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        // Release pairs with the Acquire in `lock` so protected writes are published.
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
+
+// RefCell - its safe dynamically check borrowing
 
 // to tell compiler u can never share across threads 
 // Unsafe cell is also imolements this so we can get this already 
@@ -155,71 +566,295 @@ impl<T> Drop for RefMut<'_, T> {
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    // These four are deliberately kept as compile-fail illustrations: Cell is
+    // !Sync, so sharing it across threads does not type-check, and `get` needs
+    // T: Copy. They document the single-threaded design; uncomment to see the
+    // Sync/Send and Copy-bound errors the compiler produces.
+    //
+    // #[test]
+    // fn concurrent_set() {
+    //     use std::sync::Arc;
+    //     let x = Arc::new(Cell::new(42));
+    //     let x1 = Arc::clone(&x);
+    //     std::thread::spawn(move || {
+    //         x1.set(43);
+    //     });
+    //     let x2 = Arc::clone(&x);
+    //     std::thread::spawn(move || {
+    //         x2.set(44);
+    //     });
+    // }
+    //
+    // #[test]
+    // fn set_during_get() {
+    //     let x = Cell::new(String::from("hello"));
+    //     let first = x.get();
+    //     x.set(String::new());
+    //     x.set(String::from("world"));
+    //     eprintln!("{}", first);
+    // }
+    //
+    // #[test]
+    // fn concurrent_set_take2() {
+    //     use std::sync::Arc;
+    //     let x = Arc::new(Cell::new([0; 40240]));
+    //     let x1 = Arc::clone(&x);
+    //     let jh1 = std::thread::spawn(move || {
+    //         x1.set([1; 40240]);
+    //     });
+    //     let x2 = Arc::clone(&x);
+    //     let jh2 = std::thread::spawn(move || {
+    //         x2.set([2; 40240]);
+    //     });
+    //     jh1.join().unwrap();
+    //     jh2.join().unwrap();
+    //     let xs = x.get();
+    //     for &i in xs.iter() {
+    //         eprintln!("{}", i);
+    //     }
+    // }
+    //
+    // #[test]
+    // fn concurrent_get_set() {
+    //     use std::sync::Arc;
+    //     let x = Arc::new(Cell::new(0));
+    //     let x1 = Arc::clone(&x);
+    //     let jh1 = std::thread::spawn(move || {
+    //         for _ in 0..1000000 {
+    //             let x = x1.get();
+    //             x1.set(x + 1);
+    //         }
+    //     });
+    //     let x2 = Arc::clone(&x);
+    //     let jh2 = std::thread::spawn(move || {
+    //         for _ in 0..1000000 {
+    //             let x = x2.get();
+    //             x2.set(x + 1);
+    //         }
+    //     });
+    //     jh1.join().unwrap();
+    //     jh2.join().unwrap();
+    //     assert_eq!(x.get(), 2000000);
+    // }
 
     #[test]
-fn concurrent_set() {
-    use std::sync::Arc;
-    let x = Arc::new(Cell::new(42));
-    let x1 = Arc::clone(&x);
-    std::thread::spawn(move || {
-        x1.set(43);
-    });
-    let x2 = Arc::clone(&x);
-    std::thread::spawn(move || {
-        x2.set(44);
-    });
-}
-
-#[test]
-fn set_during_get() {
-    let x = Cell::new(String::from("hello"));
-    let first = x.get();
-    x.set(String::new());
-    x.set(String::from("world"));
-    eprintln!("{}", first);
-}
-
-#[test]
-fn concurrent_set_take2() {
-    use std::sync::Arc;
-    let x = Arc::new(Cell::new([0; 40240]));
-    let x1 = Arc::clone(&x);
-    let jh1 = std::thread::spawn(move || {
-        x1.set([1; 40240]);
-    });
-    let x2 = Arc::clone(&x);
-    let jh2 = std::thread::spawn(move || {
-        x2.set([2; 40240]);
-    });
-    jh1.join().unwrap();
-    jh2.join().unwrap();
-    let xs = x.get();
-    for &i in xs.iter() {
-        eprintln!("{}", i);
-    }
-}
-
-#[test]
-fn concurrent_get_set() {
-    use std::sync::Arc;
-    let x = Arc::new(Cell::new(0));
-    let x1 = Arc::clone(&x);
-    let jh1 = std::thread::spawn(move || {
-        for _ in 0..1000000 {
-            let x = x1.get();
-            x1.set(x + 1);
-        }
-    });
-    let x2 = Arc::clone(&x);
-    let jh2 = std::thread::spawn(move || {
-        for _ in 0..1000000 {
-            let x = x2.get();
-            x2.set(x + 1);
-        }
-    });
-    jh1.join().unwrap();
-    jh2.join().unwrap();
-    assert_eq!(x.get(), 2000000);
-}
+    fn cell_get_set_roundtrip() {
+        let x = Cell::new(1);
+        x.set(5);
+        assert_eq!(x.get(), 5);
+    }
 
+    #[test]
+    fn rc_shares_and_frees_once() {
+        let a = Rc::new(42);
+        let b = Rc::clone(&a);
+        let c = a.clone();
+        assert_eq!(*a, 42);
+        assert_eq!(*b, 42);
+        assert_eq!(*c, 42);
+        // the inner count is 3 now; dropping all three must free exactly once.
+        drop(b);
+        drop(c);
+        assert_eq!(*a, 42);
+        drop(a);
+    }
+
+    #[test]
+    fn atomic_refcell_allows_many_shared() {
+        let rc = AtomicRefCell::new(10);
+        let a = rc.borrow().unwrap();
+        let b = rc.borrow().unwrap();
+        assert_eq!(*a + *b, 20);
+        // an exclusive borrow is refused while shared borrows are out
+        assert!(rc.borrow_mut().is_none());
+        drop(a);
+        drop(b);
+        // ...and succeeds once they're gone
+        *rc.borrow_mut().unwrap() += 1;
+        assert_eq!(*rc.borrow().unwrap(), 11);
+    }
+
+    #[test]
+    fn atomic_refcell_excludes_shared_during_mut() {
+        let rc = AtomicRefCell::new(0);
+        let m = rc.borrow_mut().unwrap();
+        assert!(rc.borrow().is_none());
+        drop(m);
+    }
+
+    #[test]
+    fn atomic_refcell_across_threads() {
+        use std::sync::Arc;
+        let rc = Arc::new(AtomicRefCell::new(0u64));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let rc = Arc::clone(&rc);
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        // retry until we win the exclusive borrow
+                        loop {
+                            if let Some(mut g) = rc.borrow_mut() {
+                                *g += 1;
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(*rc.borrow().unwrap(), 4000);
+    }
+
+    #[test]
+    fn atomic_refcell_mixed_borrows_dont_poison() {
+        use std::sync::Arc;
+        let rc = Arc::new(AtomicRefCell::new(0u64));
+        let mut handles = Vec::new();
+        // writers
+        for _ in 0..2 {
+            let rc = Arc::clone(&rc);
+            handles.push(std::thread::spawn(move || {
+                for _ in 0..2000 {
+                    loop {
+                        if let Some(mut g) = rc.borrow_mut() {
+                            *g += 1;
+                            break;
+                        }
+                    }
+                }
+            }));
+        }
+        // readers racing against the writers: these must never permanently
+        // poison the state even when they collide with an exclusive borrow.
+        for _ in 0..2 {
+            let rc = Arc::clone(&rc);
+            handles.push(std::thread::spawn(move || {
+                for _ in 0..2000 {
+                    if let Some(g) = rc.borrow() {
+                        let _ = *g;
+                    }
+                }
+            }));
+        }
+        for h in handles {
+            h.join().unwrap();
+        }
+        // if the cell were poisoned these would return None; they must succeed.
+        assert_eq!(*rc.borrow().unwrap(), 4000);
+        assert!(rc.borrow_mut().is_some());
+    }
+
+    #[test]
+    fn mutex_mutual_exclusion() {
+        use std::sync::Arc;
+        let m = Arc::new(Mutex::new(0u64));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let m = Arc::clone(&m);
+                std::thread::spawn(move || {
+                    for _ in 0..10000 {
+                        *m.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(*m.lock(), 40000);
+    }
+
+    #[test]
+    fn mutex_with_lock() {
+        let m = Mutex::new(vec![1, 2, 3]);
+        let len = m.with_lock(|v| {
+            v.push(4);
+            v.len()
+        });
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn spinlock_mutual_exclusion() {
+        use std::sync::Arc;
+        let s = Arc::new(SpinLock::new(0u64));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let s = Arc::clone(&s);
+                std::thread::spawn(move || {
+                    for _ in 0..10000 {
+                        s.with_lock(|n| *n += 1);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        s.with_lock(|n| assert_eq!(*n, 40000));
+    }
+
+    #[test]
+    fn ref_map_projects_and_tracks() {
+        let rc = RefCell::new((1, 2));
+        {
+            let first = Ref::map(rc.borrow(), |t| &t.0);
+            assert_eq!(*first, 1);
+            // the borrow is still tracked: a mutable borrow must fail
+            assert!(rc.try_borrow_mut().is_err());
+        }
+        // once the projected Ref drops, the counter is back to unshared
+        assert!(rc.try_borrow_mut().is_ok());
+    }
+
+    #[test]
+    fn refmut_map_projects_and_tracks() {
+        let rc = RefCell::new(vec![10, 20, 30]);
+        {
+            let mut second = RefMut::map(rc.borrow_mut(), |v| &mut v[1]);
+            *second += 5;
+            assert!(rc.try_borrow().is_err());
+        }
+        assert_eq!(rc.borrow()[1], 25);
+    }
+
+    #[test]
+    fn refcell_get_mut_skips_tracking() {
+        let mut rc = RefCell::new(7);
+        *rc.get_mut() += 1;
+        assert_eq!(*rc.borrow(), 8);
+    }
+
+    #[test]
+    fn oncecell_sets_once() {
+        let c = OnceCell::new();
+        assert!(c.get().is_none());
+        assert_eq!(c.set(1), Ok(()));
+        assert_eq!(c.get(), Some(&1));
+        // a second set is rejected and hands the value back
+        assert_eq!(c.set(2), Err(2));
+        assert_eq!(c.get(), Some(&1));
+    }
+
+    #[test]
+    fn oncecell_get_or_init_runs_once() {
+        let c = OnceCell::new();
+        let mut calls = 0;
+        let v = *c.get_or_init(|| {
+            calls += 1;
+            99
+        });
+        assert_eq!(v, 99);
+        let v = *c.get_or_init(|| {
+            calls += 1;
+            -1
+        });
+        assert_eq!(v, 99);
+        assert_eq!(calls, 1);
+    }
 }
\ No newline at end of file